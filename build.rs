@@ -2,7 +2,25 @@ extern crate cc;
 
 use std::env;
 
+/// The SIMD instruction paths this build enabled in the native
+/// `gf256`/`cauchy_256` sources, used to emit matching `rustc-cfg` flags so
+/// the Rust side can report what it was built with via
+/// `Cauchy::cpu_features()`.
+#[derive(Default)]
+struct SimdPaths {
+    ssse3: bool,
+    avx2: bool,
+    neon: bool,
+}
+
 fn main() {
+    println!("cargo::rustc-check-cfg=cfg(cauchy_simd_ssse3)");
+    println!("cargo::rustc-check-cfg=cfg(cauchy_simd_avx2)");
+    println!("cargo::rustc-check-cfg=cfg(cauchy_simd_neon)");
+
+    let target = env::var("TARGET").unwrap();
+    let arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+
     let mut builder = cc::Build::new()
         .cpp(true)
         .extra_warnings(true)
@@ -19,9 +37,59 @@ fn main() {
         .file("longhair/gf256.cpp")
         .clone();
 
-    if env::var("TARGET").unwrap().contains("arm") {
+    if target.contains("arm") {
         builder.define("LINUX_ARM", None);
     }
 
+    let simd = configure_simd(&mut builder, &arch);
+
+    if simd.ssse3 {
+        println!("cargo:rustc-cfg=cauchy_simd_ssse3");
+    }
+    if simd.avx2 {
+        println!("cargo:rustc-cfg=cauchy_simd_avx2");
+    }
+    if simd.neon {
+        println!("cargo:rustc-cfg=cauchy_simd_neon");
+    }
+
     builder.compile("longhair");
 }
+
+/// Gates target-feature flags behind the `simd`/`avx2`/`neon` Cargo
+/// features, so downstream users can trade portability for speed instead
+/// of always getting the most conservative instruction set.
+fn configure_simd(builder: &mut cc::Build, arch: &str) -> SimdPaths {
+    let simd_enabled = env::var_os("CARGO_FEATURE_SIMD").is_some();
+    let avx2_enabled = env::var_os("CARGO_FEATURE_AVX2").is_some();
+    let neon_enabled = env::var_os("CARGO_FEATURE_NEON").is_some();
+
+    let mut paths = SimdPaths::default();
+
+    match arch {
+        "x86_64" | "x86" => {
+            if avx2_enabled {
+                builder.flag_if_supported("-mavx2");
+                paths.avx2 = true;
+            } else if simd_enabled {
+                builder.flag_if_supported("-mssse3");
+                paths.ssse3 = true;
+            }
+        }
+        "aarch64" => {
+            if simd_enabled || neon_enabled {
+                builder.flag_if_supported("-march=armv8-a+simd");
+                paths.neon = true;
+            }
+        }
+        "arm" => {
+            if neon_enabled {
+                builder.flag_if_supported("-mfpu=neon");
+                paths.neon = true;
+            }
+        }
+        _ => {}
+    }
+
+    paths
+}
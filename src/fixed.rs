@@ -0,0 +1,129 @@
+//! A front-end for callers whose block size is known at compile time.
+//!
+//! `Cauchy` validates that every block is the same nonzero multiple of 8
+//! at every call, which costs a scan over the inputs and turns a mismatch
+//! into a runtime `CauchyError`. When the block size is fixed ahead of
+//! time (a packet MTU, a disk sector), [`CauchyFixed`] moves that
+//! invariant into the type via a `const N: usize` parameter: an invalid
+//! `N` fails to compile when `CauchyFixed::<N>::new` is instantiated.
+//! `encode` and `decode` still forward to `Cauchy`, so the per-call scan
+//! over the blocks and its runtime `CauchyError`s remain.
+
+use crate::{AsBlock, Cauchy, CauchyError};
+
+/// Fails to compile unless `N` is a nonzero multiple of 8.
+struct BlockSize<const N: usize>;
+
+impl<const N: usize> BlockSize<N> {
+    const VALID: () = assert!(N > 0 && N % 8 == 0, "N must be a nonzero multiple of 8");
+}
+
+/// A fixed-size block paired with its row index, ready to hand to
+/// [`CauchyFixed::decode`].
+pub struct FixedBlock<const N: usize> {
+    pub index: u32,
+    pub data: [u8; N],
+}
+
+impl<const N: usize> FixedBlock<N> {
+    pub fn new(index: u32, data: [u8; N]) -> FixedBlock<N> {
+        FixedBlock { index, data }
+    }
+}
+
+impl<const N: usize> AsBlock for FixedBlock<N> {
+    fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn index(&self) -> u32 {
+        self.index
+    }
+
+    fn set_index(&mut self, index: u32) {
+        self.index = index;
+    }
+}
+
+/// `Cauchy`, specialized to blocks of a fixed size `N`.
+pub struct CauchyFixed<const N: usize> {
+    inner: Cauchy,
+}
+
+impl<const N: usize> CauchyFixed<N> {
+    pub fn new(max_k: u32) -> Result<CauchyFixed<N>, CauchyError> {
+        let () = BlockSize::<N>::VALID;
+
+        Ok(CauchyFixed {
+            inner: Cauchy::new(max_k)?,
+        })
+    }
+
+    pub fn max_k(&self) -> u32 {
+        self.inner.max_k()
+    }
+
+    pub fn encode(
+        &mut self,
+        blocks: &[[u8; N]],
+        recovery_blocks: &mut [[u8; N]],
+    ) -> Result<(), CauchyError> {
+        self.inner.encode(blocks, recovery_blocks)
+    }
+
+    pub fn decode(
+        &mut self,
+        k: u32,
+        m: u32,
+        blocks: &mut [FixedBlock<N>],
+    ) -> Result<(), CauchyError> {
+        self.inner.decode(k, m, blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    const N: usize = 512;
+
+    fn random_block() -> [u8; N] {
+        let mut block = [0u8; N];
+        thread_rng().fill(&mut block[..]);
+        block
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let k = 4u32;
+        let m = 3u32;
+
+        let input: Vec<[u8; N]> = (0..k).map(|_| random_block()).collect();
+        let mut recovery: Vec<[u8; N]> = (0..m).map(|_| [0u8; N]).collect();
+
+        let mut cauchy = CauchyFixed::<N>::new(k).unwrap();
+        cauchy.encode(&input, &mut recovery).unwrap();
+
+        let mut blocks: Vec<FixedBlock<N>> = input
+            .iter()
+            .chain(recovery.iter())
+            .enumerate()
+            .map(|(i, data)| FixedBlock::new(i as u32, *data))
+            .collect();
+
+        thread_rng().shuffle(&mut blocks);
+        blocks.truncate(k as usize);
+
+        cauchy.decode(k, m, &mut blocks).unwrap();
+        blocks.sort_by_key(|b| b.index);
+
+        for (expected, block) in input.iter().zip(blocks.iter()) {
+            assert_eq!(expected, &block.data);
+        }
+    }
+}
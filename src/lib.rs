@@ -8,11 +8,72 @@ extern crate proptest;
 extern crate rand;
 
 use std::borrow::{Borrow, BorrowMut};
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Once, ONCE_INIT};
 
 include!("bindings.rs");
 
+pub mod fixed;
+pub mod wire;
+
 static CAUCHY_INITIALIZED: Once = ONCE_INIT;
+static CAUCHY_INIT_OK: AtomicBool = AtomicBool::new(false);
+
+/// Everything that can go wrong calling into the `Cauchy` API.
+///
+/// Every variant corresponds to a validation check or native FFI call that
+/// used to `panic!`; a codec sitting on untrusted wire data needs to be
+/// able to recover from bad input instead of aborting the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CauchyError {
+    /// `max_k` passed to `Cauchy::new` exceeds the native limit of 256.
+    KTooLarge,
+    /// More blocks were passed to `encode`/`decode` than `max_k` allows.
+    TooManyBlocks,
+    /// `decode` was passed a number of blocks other than `k`.
+    MismatchedBlockCount { expected: u32, actual: usize },
+    /// Not every block in a call had the same length.
+    MismatchedBlockSize,
+    /// A block's length was zero or not a multiple of 8.
+    InvalidBlockSize { len: usize },
+    /// A block's index was `>= k + m`.
+    IndexOutOfRange,
+    /// The native `cauchy_256_encode` call returned a nonzero status.
+    EncodeFailed,
+    /// The native `cauchy_256_decode` call returned a nonzero status.
+    DecodeFailed,
+    /// The native `_cauchy_256_init` call returned a nonzero status.
+    InitFailed,
+}
+
+impl fmt::Display for CauchyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CauchyError::KTooLarge => write!(f, "k must be <= 256"),
+            CauchyError::TooManyBlocks => write!(f, "num blocks must be <= max_k"),
+            CauchyError::MismatchedBlockCount { expected, actual } => write!(
+                f,
+                "decode needs exactly k ({}) blocks, got {}",
+                expected, actual
+            ),
+            CauchyError::MismatchedBlockSize => write!(f, "all blocks must have the same size"),
+            CauchyError::InvalidBlockSize { len } => write!(
+                f,
+                "the size of a block ({} bytes) cannot be zero and must be a multiple of 8",
+                len
+            ),
+            CauchyError::IndexOutOfRange => write!(f, "block index cannot be >= k + m"),
+            CauchyError::EncodeFailed => write!(f, "cauchy_256_encode failed"),
+            CauchyError::DecodeFailed => write!(f, "cauchy_256_decode failed"),
+            CauchyError::InitFailed => write!(f, "cauchy_256 native library failed to initialize"),
+        }
+    }
+}
+
+impl Error for CauchyError {}
 
 pub trait AsBlock {
     fn data_mut(&mut self) -> &mut [u8];
@@ -26,47 +87,92 @@ pub struct Cauchy {
     block_ptrs: Vec<*const u8>,
     recovery_block_ptrs: Vec<*mut u8>,
     native_block_ptrs: Vec<Block>,
+    padded_scratch: Vec<Vec<u8>>,
+}
+
+/// Rounds `len` up to the next multiple of 8; `0` stays `0`.
+fn pad_up(len: usize) -> usize {
+    if len % 8 == 0 {
+        len
+    } else {
+        len + (8 - len % 8)
+    }
+}
+
+/// An [`AsBlock`] over a padded scratch buffer, carrying the index of the
+/// caller's original block through `decode_padded`.
+struct PaddedBlock<'a> {
+    index: u32,
+    data: &'a mut [u8],
+}
+
+impl<'a> AsBlock for PaddedBlock<'a> {
+    fn data_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+
+    fn data(&self) -> &[u8] {
+        self.data
+    }
+
+    fn index(&self) -> u32 {
+        self.index
+    }
+
+    fn set_index(&mut self, index: u32) {
+        self.index = index;
+    }
 }
 
 impl Cauchy {
-    pub fn new(max_k: u32) -> Cauchy {
+    pub fn new(max_k: u32) -> Result<Cauchy, CauchyError> {
         if max_k > 256 {
-            panic!("k must be <= 256");
+            return Err(CauchyError::KTooLarge);
         }
 
         CAUCHY_INITIALIZED.call_once(|| unsafe {
-            if _cauchy_256_init(CAUCHY_256_VERSION as i32) != 0 {
-                panic!("cauchy initialize failed!");
-            }
+            let ok = _cauchy_256_init(CAUCHY_256_VERSION as i32) == 0;
+            CAUCHY_INIT_OK.store(ok, Ordering::SeqCst);
         });
 
-        Cauchy {
+        if !CAUCHY_INIT_OK.load(Ordering::SeqCst) {
+            return Err(CauchyError::InitFailed);
+        }
+
+        Ok(Cauchy {
             max_k,
             block_ptrs: Vec::with_capacity(max_k as usize),
             recovery_block_ptrs: Vec::with_capacity(max_k as usize),
             native_block_ptrs: Vec::with_capacity(max_k as usize),
-        }
+            padded_scratch: Vec::new(),
+        })
     }
 
     pub fn max_k(&self) -> u32 {
         self.max_k
     }
 
+    /// Grows `self.padded_scratch` to hold at least `count` buffers of
+    /// exactly `len` bytes each, reusing existing allocations where
+    /// possible instead of allocating fresh scratch on every call.
+    fn ensure_padded_scratch(&mut self, count: usize, len: usize) {
+        while self.padded_scratch.len() < count {
+            self.padded_scratch.push(Vec::new());
+        }
+        for buf in self.padded_scratch.iter_mut().take(count) {
+            buf.resize(len, 0);
+        }
+    }
+
     pub fn encode<I: Borrow<[u8]>, O: BorrowMut<[u8]>>(
         &mut self,
         blocks: &[I],
         recovery_blocks: &mut [O],
-    ) {
+    ) -> Result<(), CauchyError> {
         if blocks.len() as u32 > self.max_k {
-            panic!("num blocks must be <= max_k");
+            return Err(CauchyError::TooManyBlocks);
         }
 
-        // println!("longhair-rs: recovery_blocks {:?}", recovery_blocks);
-        // println!(
-        //     "longhair-rs: recovery_blocks.len(): {}",
-        //     recovery_blocks.len()
-        // );
-
         self.block_ptrs.clear();
 
         let mut block_bytes_opt = None;
@@ -74,7 +180,7 @@ impl Cauchy {
             let data = block.borrow();
             if let Some(block_bytes) = block_bytes_opt {
                 if data.len() != block_bytes {
-                    panic!("all blocks must have the same size");
+                    return Err(CauchyError::MismatchedBlockSize);
                 }
             } else {
                 block_bytes_opt = Some(data.len());
@@ -83,27 +189,20 @@ impl Cauchy {
             self.block_ptrs.push(data.as_ptr() as *const u8);
         }
 
-        let block_bytes = block_bytes_opt.unwrap();
+        let block_bytes = block_bytes_opt.ok_or(CauchyError::InvalidBlockSize { len: 0 })?;
         if block_bytes == 0 || block_bytes % 8 != 0 {
-            panic!("the size of data blocks cannot be zero and must be a multiple of 8");
+            return Err(CauchyError::InvalidBlockSize { len: block_bytes });
         }
 
         self.recovery_block_ptrs.clear();
         for recovery_block in recovery_blocks {
             let data = recovery_block.borrow_mut();
             if block_bytes != data.len() {
-                panic!("all blocks must have the same size");
+                return Err(CauchyError::MismatchedBlockSize);
             }
             self.recovery_block_ptrs.push(data.as_mut_ptr() as *mut u8);
         }
 
-        // println!("longhair-rs: block_bytes: {}", block_bytes);
-        // println!("longhair-rs: block_ptrs: {:?}", self.block_ptrs);
-        // println!(
-        //     "longhair-rs: recovery_block_ptrs: {:?}",
-        //     self.recovery_block_ptrs
-        // );
-
         let block_ptrs = self.block_ptrs.as_ptr() as *mut *const u8;
         let buf_ptr = self.recovery_block_ptrs.as_mut_ptr() as *mut *mut u8;
 
@@ -118,16 +217,81 @@ impl Cauchy {
         };
 
         if result != 0 {
-            panic!("cauchy encode failed!");
+            return Err(CauchyError::EncodeFailed);
+        }
+
+        Ok(())
+    }
+
+    /// Like [`Cauchy::encode`], but accepts data blocks of any nonzero
+    /// length instead of requiring a multiple of 8.
+    ///
+    /// Each data block is copied into a crate-owned, zero-padded scratch
+    /// buffer sized to the next multiple of 8 and encoded from there; the
+    /// scratch buffers are reused across calls. `recovery_blocks` are
+    /// written to directly and must each be exactly `pad_up(original_len)`
+    /// bytes: longhair is bit-sliced across 8 sub-rows per block, so a
+    /// recovery block's padding bytes are real codec output, not zero
+    /// filler, and must be stored and handed back to [`Cauchy::decode`] (or
+    /// [`Cauchy::decode_padded`]) in full rather than trimmed to
+    /// `original_len`.
+    pub fn encode_padded<I: Borrow<[u8]>, O: BorrowMut<[u8]>>(
+        &mut self,
+        blocks: &[I],
+        recovery_blocks: &mut [O],
+    ) -> Result<(), CauchyError> {
+        let original_len = match blocks.first() {
+            Some(block) => block.borrow().len(),
+            None => return Err(CauchyError::InvalidBlockSize { len: 0 }),
+        };
+        if original_len == 0 {
+            return Err(CauchyError::InvalidBlockSize { len: 0 });
         }
+        let padded_len = pad_up(original_len);
+
+        self.ensure_padded_scratch(blocks.len(), padded_len);
+        let mut scratch = mem::take(&mut self.padded_scratch);
+
+        let fill_result = (|| {
+            for (padded, block) in scratch.iter_mut().zip(blocks.iter()) {
+                let data = block.borrow();
+                if data.len() != original_len {
+                    return Err(CauchyError::MismatchedBlockSize);
+                }
+                padded[..original_len].copy_from_slice(data);
+                for byte in &mut padded[original_len..] {
+                    *byte = 0;
+                }
+            }
+            for recovery_block in recovery_blocks.iter() {
+                if recovery_block.borrow().len() != padded_len {
+                    return Err(CauchyError::MismatchedBlockSize);
+                }
+            }
+            Ok(())
+        })();
+
+        let result =
+            fill_result.and_then(|()| self.encode(&scratch[..blocks.len()], recovery_blocks));
+
+        self.padded_scratch = scratch;
+        result
     }
 
-    pub fn decode<B: AsBlock>(&mut self, k: u32, m: u32, blocks: &mut [B]) {
+    pub fn decode<B: AsBlock>(
+        &mut self,
+        k: u32,
+        m: u32,
+        blocks: &mut [B],
+    ) -> Result<(), CauchyError> {
         if k > self.max_k {
-            panic!("k must be <= max_k");
+            return Err(CauchyError::TooManyBlocks);
         }
         if blocks.len() != k as usize {
-            panic!("blocks len must be the same as k");
+            return Err(CauchyError::MismatchedBlockCount {
+                expected: k,
+                actual: blocks.len(),
+            });
         }
 
         self.native_block_ptrs.clear();
@@ -138,14 +302,14 @@ impl Cauchy {
             let data = block.data_mut();
             if let Some(block_bytes) = block_bytes_opt {
                 if data.len() != block_bytes {
-                    panic!("all blocks must have the same size");
+                    return Err(CauchyError::MismatchedBlockSize);
                 }
             } else {
                 block_bytes_opt = Some(data.len());
             }
 
             if index >= k + m {
-                panic!("block number cannot be >= k + m");
+                return Err(CauchyError::IndexOutOfRange);
             }
 
             self.native_block_ptrs.push(Block {
@@ -154,9 +318,9 @@ impl Cauchy {
             });
         }
 
-        let block_bytes = block_bytes_opt.unwrap();
+        let block_bytes = block_bytes_opt.ok_or(CauchyError::InvalidBlockSize { len: 0 })?;
         if block_bytes == 0 || block_bytes % 8 != 0 {
-            panic!("the size of blocks cannot be zero and must be a multiple of 8");
+            return Err(CauchyError::InvalidBlockSize { len: block_bytes });
         }
 
         let block_ptrs = self.native_block_ptrs.as_mut_ptr();
@@ -165,12 +329,107 @@ impl Cauchy {
             unsafe { cauchy_256_decode(k as i32, m as i32, block_ptrs, block_bytes as i32) };
 
         if result != 0 {
-            panic!("cauchy decode failed!");
+            return Err(CauchyError::DecodeFailed);
         }
 
         for (input_block, native_block) in blocks.iter_mut().zip(self.native_block_ptrs.iter()) {
             input_block.set_index(native_block.row as u32);
         }
+
+        Ok(())
+    }
+
+    /// Like [`Cauchy::decode`], but accepts data blocks of any nonzero
+    /// length instead of requiring a multiple of 8.
+    ///
+    /// `original_len` is the true, unpadded data block length that was
+    /// passed to `encode_padded`. Surviving data blocks (`index < k`) are
+    /// `original_len` bytes and are zero-padded into a scratch buffer;
+    /// recovery blocks (`index >= k`) are the full `pad_up(original_len)`
+    /// bytes [`Cauchy::encode_padded`] wrote out and are copied in as-is,
+    /// since their padding carries real codec output rather than zero
+    /// filler. The scratch buffers are reused across calls.
+    pub fn decode_padded<B: AsBlock>(
+        &mut self,
+        k: u32,
+        m: u32,
+        blocks: &mut [B],
+        original_len: usize,
+    ) -> Result<(), CauchyError> {
+        if original_len == 0 {
+            return Err(CauchyError::InvalidBlockSize { len: 0 });
+        }
+        let padded_len = pad_up(original_len);
+
+        self.ensure_padded_scratch(blocks.len(), padded_len);
+        let mut scratch = mem::take(&mut self.padded_scratch);
+
+        let fill_result = (|| {
+            for (padded, block) in scratch.iter_mut().zip(blocks.iter()) {
+                let data = block.data();
+                if block.index() < k {
+                    if data.len() != original_len {
+                        return Err(CauchyError::MismatchedBlockSize);
+                    }
+                    padded[..original_len].copy_from_slice(data);
+                    for byte in &mut padded[original_len..] {
+                        *byte = 0;
+                    }
+                } else {
+                    if data.len() != padded_len {
+                        return Err(CauchyError::MismatchedBlockSize);
+                    }
+                    padded.copy_from_slice(data);
+                }
+            }
+            Ok(())
+        })();
+
+        let result = fill_result.and_then(|()| {
+            let mut padded_blocks: Vec<PaddedBlock> = scratch
+                .iter_mut()
+                .zip(blocks.iter())
+                .map(|(padded, block)| PaddedBlock {
+                    index: block.index(),
+                    data: &mut padded[..],
+                })
+                .collect();
+
+            self.decode(k, m, &mut padded_blocks)?;
+
+            for (block, padded) in blocks.iter_mut().zip(padded_blocks.iter()) {
+                block.data_mut()[..original_len].copy_from_slice(&padded.data()[..original_len]);
+                block.set_index(padded.index());
+            }
+
+            Ok(())
+        });
+
+        self.padded_scratch = scratch;
+        result
+    }
+}
+
+/// Which SIMD instruction paths the native `gf256`/`cauchy_256` sources
+/// were compiled with, as decided by `build.rs` from the `simd`/`avx2`/
+/// `neon` Cargo features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CpuFeatures {
+    pub ssse3: bool,
+    pub avx2: bool,
+    pub neon: bool,
+}
+
+impl Cauchy {
+    /// Reports which SIMD paths this build of the crate was compiled
+    /// with. Purely informational: the native library picks the fastest
+    /// compiled-in path on its own at runtime.
+    pub fn cpu_features() -> CpuFeatures {
+        CpuFeatures {
+            ssse3: cfg!(cauchy_simd_ssse3),
+            avx2: cfg!(cauchy_simd_avx2),
+            neon: cfg!(cauchy_simd_neon),
+        }
     }
 }
 
@@ -244,9 +503,9 @@ mod tests {
             prop_assume!(input_blocks + output_blocks <= 255);
 
             let mut output = empty_blocks(block_size, output_blocks);
-            let mut cauchy = Cauchy::new(input_blocks as u32);
+            let mut cauchy = Cauchy::new(input_blocks as u32).unwrap();
 
-            cauchy.encode(&input, &mut output);
+            cauchy.encode(&input, &mut output).unwrap();
 
             let mut input_cloned = input.clone();
             let mut output_with_indices = input_cloned.iter_mut().chain(output.iter_mut())
@@ -255,7 +514,7 @@ mod tests {
                                         .collect::<Vec<_>>();
             thread_rng().shuffle(&mut output_with_indices);
             output_with_indices.truncate(input_blocks as usize);
-            cauchy.decode(input_blocks, output_blocks, &mut output_with_indices[..]);
+            cauchy.decode(input_blocks, output_blocks, &mut output_with_indices[..]).unwrap();
             output_with_indices.sort_by_key(|&(i,_)|i);
 
             for (expected_index, expected_block) in input.iter().enumerate() {
@@ -265,4 +524,65 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn padded_round_trip() {
+        let input_blocks: Vec<Block> = vec![
+            b"hello".to_vec().into_boxed_slice(),
+            b"world".to_vec().into_boxed_slice(),
+            b"abcde".to_vec().into_boxed_slice(),
+        ];
+        let original_len = input_blocks[0].len();
+        let padded_len = pad_up(original_len);
+        let output_blocks = 2u32;
+
+        let mut output = empty_blocks(padded_len, output_blocks);
+        let mut cauchy = Cauchy::new(input_blocks.len() as u32).unwrap();
+
+        cauchy.encode_padded(&input_blocks, &mut output).unwrap();
+
+        let mut input_cloned = input_blocks.clone();
+        let all_blocks = input_cloned
+            .iter_mut()
+            .chain(output.iter_mut())
+            .enumerate()
+            .map(|(i, b)| (i as u32, b))
+            .collect::<Vec<_>>();
+
+        // Deterministically drop data block 1 and decode through recovery
+        // block 3 instead of block 4, so this test always exercises the
+        // path where a recovery block's padding tail must survive
+        // unmodified rather than, by chance, only ever decoding from
+        // surviving data blocks.
+        let mut survivors: Vec<_> = all_blocks.into_iter().filter(|&(i, _)| i != 1).collect();
+        survivors.truncate(input_blocks.len());
+
+        cauchy
+            .decode_padded(
+                input_blocks.len() as u32,
+                output_blocks,
+                &mut survivors[..],
+                original_len,
+            )
+            .unwrap();
+        survivors.sort_by_key(|&(i, _)| i);
+
+        for (expected_index, expected_block) in input_blocks.iter().enumerate() {
+            let &(actual_index, ref actual_block) = &survivors[expected_index];
+            assert_eq!(expected_index as u32, actual_index);
+            assert_eq!(&expected_block[..], &actual_block[..original_len]);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "avx2", any(target_arch = "x86_64", target_arch = "x86")))]
+    fn cpu_features_reports_avx2_when_enabled() {
+        assert!(Cauchy::cpu_features().avx2);
+    }
+
+    #[test]
+    #[cfg(all(feature = "neon", any(target_arch = "aarch64", target_arch = "arm")))]
+    fn cpu_features_reports_neon_when_enabled() {
+        assert!(Cauchy::cpu_features().neon);
+    }
 }
@@ -0,0 +1,323 @@
+//! Self-describing framing for encoded blocks.
+//!
+//! `Cauchy::encode` hands back bare payload bytes; a caller shipping those
+//! bytes over a socket still has to invent a header carrying `k`, `m` and
+//! the block's row so the far side can reconstruct a call to
+//! `Cauchy::decode`. This module does that bookkeeping: [`frame_block`]
+//! prepends a small fixed header to a block, and [`unframe`] walks a
+//! buffer of such frames back apart, borrowing payloads out of the input
+//! slice rather than copying them.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::{AsBlock, CAUCHY_256_VERSION};
+
+/// Size in bytes of the header written before every framed block.
+pub const HEADER_LEN: usize = 8;
+
+/// Everything that can go wrong framing or unframing wire data.
+///
+/// Unlike `CauchyError`, these are parse errors over attacker-controlled
+/// bytes rather than API-misuse checks, so they get their own type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireError {
+    /// A block was too large to record its length in the header.
+    BlockTooLarge { len: usize },
+    /// The buffer ended in the middle of a header or payload.
+    Truncated,
+    /// Frames in the same buffer disagreed on `version`, `k` or `m`.
+    Mismatched,
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            WireError::BlockTooLarge { len } => {
+                write!(f, "block of {} bytes is too large to frame", len)
+            }
+            WireError::Truncated => write!(f, "buffer ended mid-frame"),
+            WireError::Mismatched => write!(f, "frames disagree on version/k/m"),
+        }
+    }
+}
+
+impl Error for WireError {}
+
+/// A growable byte buffer that owns framed output.
+///
+/// Kept separate from `Vec<u8>` so the writing side only ever goes through
+/// [`Writer`], which is the one place that knows the wire layout.
+#[derive(Debug, Default)]
+pub struct Buffer {
+    bytes: Vec<u8>,
+}
+
+impl Buffer {
+    pub fn new() -> Buffer {
+        Buffer { bytes: Vec::new() }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Buffer {
+        Buffer {
+            bytes: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    fn writer(&mut self) -> Writer<'_> {
+        Writer {
+            bytes: &mut self.bytes,
+        }
+    }
+}
+
+/// Thin append-only cursor over a `Buffer`'s backing storage.
+struct Writer<'a> {
+    bytes: &'a mut Vec<u8>,
+}
+
+impl<'a> Writer<'a> {
+    fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn write_u32_le(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_bytes(&mut self, value: &[u8]) {
+        self.bytes.extend_from_slice(value);
+    }
+}
+
+/// Thin read-only cursor over a borrowed byte slice.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn read_u8(&mut self) -> Result<u8, WireError> {
+        let byte = *self.bytes.get(self.pos).ok_or(WireError::Truncated)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn read_u32_le(&mut self) -> Result<u32, WireError> {
+        let slice = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .ok_or(WireError::Truncated)?;
+        self.pos += 4;
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(slice);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], WireError> {
+        let slice = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or(WireError::Truncated)?;
+        self.pos += len;
+        Ok(slice)
+    }
+}
+
+/// A single framed block, parsed out of a wire buffer.
+///
+/// `payload` borrows directly from the buffer passed to [`unframe`], so
+/// parsing a set of frames does not copy any block data.
+#[derive(Debug, Clone, Copy)]
+pub struct Frame<'a> {
+    pub version: u8,
+    pub k: u8,
+    pub m: u8,
+    pub row: u8,
+    pub payload: &'a [u8],
+}
+
+/// Appends one framed block (header + payload) to `buffer`.
+///
+/// `k` and `m` describe the whole encode/decode session the block belongs
+/// to, and `row` is the block's index as used by [`AsBlock::index`].
+pub fn frame_block(
+    buffer: &mut Buffer,
+    k: u8,
+    m: u8,
+    row: u8,
+    block: &[u8],
+) -> Result<(), WireError> {
+    if block.len() > u32::max_value() as usize {
+        return Err(WireError::BlockTooLarge { len: block.len() });
+    }
+
+    let mut writer = buffer.writer();
+    writer.write_u8(CAUCHY_256_VERSION as u8);
+    writer.write_u8(k);
+    writer.write_u8(m);
+    writer.write_u32_le(block.len() as u32);
+    writer.write_u8(row);
+    writer.write_bytes(block);
+
+    Ok(())
+}
+
+/// Frames every block in `blocks`, each paired with its row index, into a
+/// single buffer ready to write to a socket.
+pub fn frame_blocks<'a, I>(k: u8, m: u8, blocks: I) -> Result<Buffer, WireError>
+where
+    I: IntoIterator<Item = (u8, &'a [u8])>,
+{
+    let mut buffer = Buffer::new();
+    for (row, block) in blocks {
+        frame_block(&mut buffer, k, m, row, block)?;
+    }
+    Ok(buffer)
+}
+
+/// Parses `data` into the frames it contains, borrowing each payload from
+/// `data` rather than copying it.
+///
+/// Returns `Err` if the buffer is truncated mid-frame, or if the frames
+/// don't all agree on `version`, `k` and `m` — both of which are expected
+/// failure modes for data read off an untrusted socket, not bugs.
+pub fn unframe(data: &[u8]) -> Result<Vec<Frame<'_>>, WireError> {
+    let mut reader = Reader::new(data);
+    let mut frames = Vec::new();
+
+    while reader.remaining() > 0 {
+        let version = reader.read_u8()?;
+        let k = reader.read_u8()?;
+        let m = reader.read_u8()?;
+        let block_bytes = reader.read_u32_le()?;
+        let row = reader.read_u8()?;
+        let payload = reader.read_bytes(block_bytes as usize)?;
+
+        if let Some(first) = frames.first() {
+            let first: &Frame = first;
+            if version != first.version || k != first.k || m != first.m {
+                return Err(WireError::Mismatched);
+            }
+        }
+
+        frames.push(Frame {
+            version,
+            k,
+            m,
+            row,
+            payload,
+        });
+    }
+
+    Ok(frames)
+}
+
+/// An owned block recovered from a [`Frame`], ready to hand to
+/// `Cauchy::decode`.
+pub struct FramedBlock {
+    index: u32,
+    data: Vec<u8>,
+}
+
+impl AsBlock for FramedBlock {
+    fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn index(&self) -> u32 {
+        self.index
+    }
+
+    fn set_index(&mut self, index: u32) {
+        self.index = index;
+    }
+}
+
+/// Copies each frame's payload into an owned, mutable [`FramedBlock`],
+/// grouped in the order the frames were parsed.
+///
+/// Decoding fills in missing blocks in place, which needs owned storage;
+/// this is the one copy in an otherwise zero-copy parse.
+pub fn frames_to_blocks(frames: &[Frame]) -> Vec<FramedBlock> {
+    frames
+        .iter()
+        .map(|frame| FramedBlock {
+            index: frame.row as u32,
+            data: frame.payload.to_vec(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cauchy;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn round_trip_through_frames() {
+        let k = 3u8;
+        let m = 2u8;
+        let block_size = 16;
+
+        let input: Vec<Vec<u8>> = (0..k).map(|i| vec![i; block_size]).collect();
+        let mut recovery: Vec<Vec<u8>> = (0..m).map(|_| vec![0u8; block_size]).collect();
+
+        let mut cauchy = Cauchy::new(k as u32).unwrap();
+        cauchy.encode(&input, &mut recovery).unwrap();
+
+        let all_blocks = input
+            .iter()
+            .chain(recovery.iter())
+            .enumerate()
+            .map(|(i, b)| (i as u8, b.as_slice()));
+
+        let buffer = frame_blocks(k, m, all_blocks).unwrap();
+        let bytes = buffer.into_bytes();
+
+        let mut frames = unframe(&bytes).unwrap();
+        assert_eq!(frames.len(), (k + m) as usize);
+
+        thread_rng().shuffle(&mut frames);
+        frames.truncate(k as usize);
+
+        let mut blocks = frames_to_blocks(&frames);
+        cauchy.decode(k as u32, m as u32, &mut blocks).unwrap();
+        blocks.sort_by_key(|b| b.index());
+
+        for (expected, block) in input.iter().zip(blocks.iter()) {
+            assert_eq!(expected.as_slice(), block.data());
+        }
+    }
+
+    #[test]
+    fn unframe_rejects_truncated_input() {
+        let mut buffer = Buffer::new();
+        frame_block(&mut buffer, 3, 2, 0, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let mut bytes = buffer.into_bytes();
+        bytes.truncate(bytes.len() - 1);
+
+        assert!(matches!(unframe(&bytes), Err(WireError::Truncated)));
+    }
+}